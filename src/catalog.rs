@@ -0,0 +1,20 @@
+use crate::schema::{Schema, Table};
+
+/// Read-only access to table and column metadata, independent of whether
+/// it comes from the in-memory `Schema` built up while planning a
+/// migration or from introspecting a live database.
+///
+/// Actions take a `&dyn Catalog` wherever they previously took a `&Schema`
+/// just to look something up, keeping catalog lookups separate from the
+/// `Conn` used to actually execute DDL. This lets a migration be planned
+/// and validated against a `Catalog` alone, with no open connection or
+/// transaction.
+pub trait Catalog {
+    fn find_table(&self, schema: Option<&str>, name: &str) -> anyhow::Result<&Table>;
+}
+
+impl Catalog for Schema {
+    fn find_table(&self, schema: Option<&str>, name: &str) -> anyhow::Result<&Table> {
+        Schema::find_table(self, schema, name)
+    }
+}
@@ -0,0 +1,435 @@
+use crate::{
+    db::Conn,
+    migrations::{
+        add_column::AddColumn,
+        add_foreign_key::{default_constraint_name, AddForeignKey},
+        alter_column::AlterColumn,
+        alter_primary_key::AlterPrimaryKey,
+        create_table::{CreateTable, ForeignKey},
+        drop_foreign_key::DropForeignKey,
+        drop_table::DropTable,
+        remove_column::RemoveColumn,
+        Action, Column,
+    },
+    schema::{Schema, Table},
+};
+
+/// Pairs of a shorthand type name a declared schema might use and the
+/// canonical spelling `information_schema.columns.data_type` actually
+/// reports for it, so a declared schema doesn't trigger a spurious
+/// `ALTER COLUMN ... TYPE` just because the two sides spell the same type
+/// differently. The canonical (right-hand) side must match what
+/// `introspect_schema` puts in `Column::data_type`, since that's the only
+/// source `normalize_type` ever sees it compared against.
+const TYPE_ALIASES: &[(&str, &str)] = &[
+    ("int4", "integer"),
+    ("int8", "bigint"),
+    ("int2", "smallint"),
+    ("varchar", "character varying"),
+    ("bool", "boolean"),
+    ("float4", "real"),
+    ("float8", "double precision"),
+    ("timestamp", "timestamp without time zone"),
+    ("timestamptz", "timestamp with time zone"),
+];
+
+fn normalize_type(data_type: &str) -> String {
+    let data_type = data_type.trim().to_lowercase();
+
+    // Ignore a length/precision modifier, e.g. "character varying(255)" or
+    // "numeric(10, 2)", which a declared schema might include but
+    // information_schema.columns.data_type never reports.
+    let data_type = match data_type.find('(') {
+        Some(index) => data_type[..index].trim().to_string(),
+        None => data_type,
+    };
+
+    for (alias, canonical) in TYPE_ALIASES {
+        if data_type == *alias {
+            return (*canonical).to_string();
+        }
+    }
+
+    data_type
+}
+
+fn types_compatible(a: &str, b: &str) -> bool {
+    normalize_type(a) == normalize_type(b)
+}
+
+/// Introspect the connected database's current schema from the
+/// `information_schema` views, so it can be diffed against a schema the
+/// user declares. `pg_catalog` itself is not queried.
+pub fn introspect_schema(db: &mut dyn Conn) -> anyhow::Result<Schema> {
+    let mut schema = Schema::new();
+
+    let table_rows = db.query(
+        "
+        SELECT table_schema, table_name
+        FROM information_schema.tables
+        WHERE table_schema NOT IN ('pg_catalog', 'information_schema')
+        ",
+    )?;
+
+    for row in &table_rows {
+        let table_schema: String = row.get("table_schema");
+        let table_name: String = row.get("table_name");
+
+        let mut table = Table::new(table_name.clone());
+        table.schema = Some(table_schema.clone());
+
+        let column_rows = db.query(&format!(
+            "
+            SELECT column_name, data_type, is_nullable
+            FROM information_schema.columns
+            WHERE table_schema = '{schema}' AND table_name = '{table}'
+            ORDER BY ordinal_position
+            ",
+            schema = table_schema,
+            table = table_name,
+        ))?;
+
+        for column_row in &column_rows {
+            let column_name: String = column_row.get("column_name");
+            let data_type: String = column_row.get("data_type");
+            let is_nullable: String = column_row.get("is_nullable");
+
+            table.add_column(crate::schema::Column {
+                name: column_name,
+                real_name: None,
+                data_type,
+                nullable: is_nullable == "YES",
+            });
+        }
+
+        table.primary_key = primary_key_columns(db, &table_schema, &table_name)?;
+        table.foreign_keys = foreign_keys(db, &table_schema, &table_name)?;
+
+        schema.add_table(table);
+    }
+
+    Ok(schema)
+}
+
+fn primary_key_columns(
+    db: &mut dyn Conn,
+    table_schema: &str,
+    table_name: &str,
+) -> anyhow::Result<Vec<String>> {
+    let rows = db.query(&format!(
+        "
+        SELECT kcu.column_name
+        FROM information_schema.table_constraints tc
+        JOIN information_schema.key_column_usage kcu
+            ON tc.constraint_name = kcu.constraint_name AND tc.table_schema = kcu.table_schema
+        WHERE tc.constraint_type = 'PRIMARY KEY'
+            AND tc.table_schema = '{schema}' AND tc.table_name = '{table}'
+        ORDER BY kcu.ordinal_position
+        ",
+        schema = table_schema,
+        table = table_name,
+    ))?;
+
+    Ok(rows.iter().map(|row| row.get("column_name")).collect())
+}
+
+fn foreign_keys(
+    db: &mut dyn Conn,
+    table_schema: &str,
+    table_name: &str,
+) -> anyhow::Result<Vec<ForeignKey>> {
+    // Pair each local column with the referenced column at the same
+    // ordinal position in the unique/primary key it points to, via
+    // referential_constraints, rather than joining on constraint name and
+    // table_schema alone: constraint_column_usage's table_schema is the
+    // *referenced* table's schema, not the child's, so that join silently
+    // drops every cross-schema foreign key.
+    let rows = db.query(&format!(
+        "
+        SELECT kcu.constraint_name, kcu.column_name,
+            ccu.table_schema AS referenced_schema, ccu.table_name AS referenced_table,
+            ccu.column_name AS referenced_column
+        FROM information_schema.referential_constraints rc
+        JOIN information_schema.key_column_usage kcu
+            ON kcu.constraint_name = rc.constraint_name AND kcu.constraint_schema = rc.constraint_schema
+        JOIN information_schema.key_column_usage ccu
+            ON ccu.constraint_name = rc.unique_constraint_name
+            AND ccu.constraint_schema = rc.unique_constraint_schema
+            AND ccu.ordinal_position = kcu.ordinal_position
+        WHERE kcu.table_schema = '{schema}' AND kcu.table_name = '{table}'
+        ORDER BY kcu.constraint_name, kcu.ordinal_position
+        ",
+        schema = table_schema,
+        table = table_name,
+    ))?;
+
+    // Rows come back one-per-column, several rows to a constraint; group
+    // them back into one ForeignKey per constraint_name, keeping each
+    // side's columns in ordinal order.
+    let mut by_constraint: Vec<(String, ForeignKey)> = Vec::new();
+    for row in &rows {
+        let constraint_name: String = row.get("constraint_name");
+        let column_name: String = row.get("column_name");
+        let referenced_schema: String = row.get("referenced_schema");
+        let referenced_table: String = row.get("referenced_table");
+        let referenced_column: String = row.get("referenced_column");
+
+        match by_constraint
+            .iter_mut()
+            .find(|(name, _)| *name == constraint_name)
+        {
+            Some((_, fk)) => {
+                fk.columns.push(column_name);
+                fk.referenced_columns.push(referenced_column);
+            }
+            None => by_constraint.push((
+                constraint_name,
+                ForeignKey {
+                    columns: vec![column_name],
+                    referenced_schema: Some(referenced_schema),
+                    referenced_table,
+                    referenced_columns: vec![referenced_column],
+                },
+            )),
+        }
+    }
+
+    Ok(by_constraint.into_iter().map(|(_, fk)| fk).collect())
+}
+
+/// Diff a declared target schema against the live schema and return the
+/// migration needed to reconcile them, expressed as the normal set of
+/// actions a hand-written migration would use.
+pub fn diff_schemas(live: &Schema, target: &Schema) -> Vec<Box<dyn Action>> {
+    let mut actions: Vec<Box<dyn Action>> = Vec::new();
+
+    for target_table in &target.tables {
+        let live_table = live
+            .tables
+            .iter()
+            .find(|table| table.name == target_table.name && table.schema == target_table.schema);
+
+        match live_table {
+            None => actions.push(Box::new(CreateTable {
+                name: target_table.name.clone(),
+                schema: target_table.schema.clone(),
+                columns: target_table
+                    .columns
+                    .iter()
+                    .map(|column| Column {
+                        name: column.name.clone(),
+                        data_type: column.data_type.clone(),
+                        nullable: column.nullable,
+                        default: None,
+                    })
+                    .collect(),
+                primary_key: target_table.primary_key.clone(),
+                foreign_keys: target_table.foreign_keys.clone(),
+                unique: Vec::new(),
+                checks: Vec::new(),
+            })),
+            Some(live_table) => actions.extend(diff_table(live_table, target_table)),
+        }
+    }
+
+    for live_table in &live.tables {
+        let still_declared = target
+            .tables
+            .iter()
+            .any(|table| table.name == live_table.name && table.schema == live_table.schema);
+
+        if !still_declared {
+            actions.push(Box::new(DropTable {
+                name: live_table.name.clone(),
+                schema: live_table.schema.clone(),
+            }));
+        }
+    }
+
+    actions
+}
+
+fn diff_table(live: &Table, target: &Table) -> Vec<Box<dyn Action>> {
+    let mut actions: Vec<Box<dyn Action>> = Vec::new();
+
+    if live.primary_key != target.primary_key {
+        actions.push(Box::new(AlterPrimaryKey {
+            table: target.name.clone(),
+            schema: target.schema.clone(),
+            columns: target.primary_key.clone(),
+            previous_columns: Some(live.primary_key.clone()),
+        }));
+    }
+
+    // Drop before add: a foreign key whose local columns are unchanged but
+    // whose referenced table/columns changed gets a different generated
+    // name (see default_constraint_name), but dropping first is cheap
+    // insurance against a name collision regardless.
+    for live_fk in &live.foreign_keys {
+        if !target.foreign_keys.contains(live_fk) {
+            actions.push(Box::new(DropForeignKey {
+                table: target.name.clone(),
+                schema: target.schema.clone(),
+                name: default_constraint_name(&target.name, live_fk),
+            }));
+        }
+    }
+
+    for target_fk in &target.foreign_keys {
+        if !live.foreign_keys.contains(target_fk) {
+            actions.push(Box::new(AddForeignKey {
+                table: target.name.clone(),
+                schema: target.schema.clone(),
+                name: default_constraint_name(&target.name, target_fk),
+                foreign_key: target_fk.clone(),
+            }));
+        }
+    }
+
+    for target_column in &target.columns {
+        match live.columns.iter().find(|c| c.name == target_column.name) {
+            None => actions.push(Box::new(AddColumn {
+                table: target.name.clone(),
+                schema: target.schema.clone(),
+                column: Column {
+                    name: target_column.name.clone(),
+                    data_type: target_column.data_type.clone(),
+                    nullable: target_column.nullable,
+                    default: None,
+                },
+                up: None,
+            })),
+            Some(live_column) => {
+                if !types_compatible(&live_column.data_type, &target_column.data_type) {
+                    actions.push(Box::new(AlterColumn {
+                        table: target.name.clone(),
+                        schema: target.schema.clone(),
+                        column: target_column.name.clone(),
+                        data_type: target_column.data_type.clone(),
+                        using: None,
+                        previous_data_type: Some(live_column.data_type.clone()),
+                    }));
+                }
+            }
+        }
+    }
+
+    for live_column in &live.columns {
+        if !target.columns.iter().any(|c| c.name == live_column.name) {
+            actions.push(Box::new(RemoveColumn {
+                table: target.name.clone(),
+                schema: target.schema.clone(),
+                column: live_column.name.clone(),
+                down: None,
+            }));
+        }
+    }
+
+    actions
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::catalog::Catalog;
+
+    fn table(name: &str) -> Table {
+        Table::new(name.to_string())
+    }
+
+    fn describe_all(actions: &[Box<dyn Action>]) -> Vec<String> {
+        let catalog: &dyn Catalog = &Schema::new();
+        actions
+            .iter()
+            .map(|action| action.describe(catalog))
+            .collect()
+    }
+
+    #[test]
+    fn normalize_type_maps_pg_catalog_short_names() {
+        assert_eq!(normalize_type("int4"), "integer");
+        assert_eq!(normalize_type("bool"), "boolean");
+    }
+
+    #[test]
+    fn normalize_type_maps_varchar_both_ways() {
+        // information_schema.columns reports "character varying" for a
+        // varchar column, not the pg_catalog short name "varchar" - both
+        // spellings must normalize to the same thing.
+        assert_eq!(normalize_type("varchar"), normalize_type("character varying"));
+    }
+
+    #[test]
+    fn normalize_type_ignores_length_modifier() {
+        assert_eq!(
+            normalize_type("character varying(255)"),
+            normalize_type("character varying")
+        );
+    }
+
+    #[test]
+    fn diff_table_detects_primary_key_change() {
+        let mut live = table("users");
+        live.primary_key = vec!["id".to_string()];
+        let mut target = table("users");
+        target.primary_key = vec!["id".to_string(), "tenant_id".to_string()];
+
+        let actions = diff_table(&live, &target);
+
+        assert_eq!(
+            describe_all(&actions),
+            vec!["Altering primary key of \"users\" to (id, tenant_id)"]
+        );
+    }
+
+    #[test]
+    fn diff_table_is_empty_when_identical_foreign_key_is_unchanged() {
+        // Regression test: referenced_columns and referenced_schema must
+        // be populated for an introspected live FK to compare equal to
+        // the same FK declared in the target schema.
+        let fk = ForeignKey {
+            columns: vec!["account_id".to_string()],
+            referenced_schema: Some("public".to_string()),
+            referenced_table: "accounts".to_string(),
+            referenced_columns: vec!["id".to_string()],
+        };
+        let mut live = table("orders");
+        live.foreign_keys = vec![fk.clone()];
+        let mut target = table("orders");
+        target.foreign_keys = vec![fk];
+
+        assert!(diff_table(&live, &target).is_empty());
+    }
+
+    #[test]
+    fn diff_table_changes_what_a_foreign_key_points_to() {
+        let mut live = table("orders");
+        live.foreign_keys = vec![ForeignKey {
+            columns: vec!["account_id".to_string()],
+            referenced_schema: Some("public".to_string()),
+            referenced_table: "old_accounts".to_string(),
+            referenced_columns: vec!["id".to_string()],
+        }];
+        let mut target = table("orders");
+        target.foreign_keys = vec![ForeignKey {
+            columns: vec!["account_id".to_string()],
+            referenced_schema: Some("public".to_string()),
+            referenced_table: "accounts".to_string(),
+            referenced_columns: vec!["id".to_string()],
+        }];
+
+        let actions = diff_table(&live, &target);
+        let descriptions = describe_all(&actions);
+
+        // The drop must come first, and the generated names for the old
+        // and new definitions must differ, or the add would collide with
+        // the constraint the drop hasn't removed yet.
+        assert_eq!(descriptions.len(), 2);
+        assert!(descriptions[0].starts_with("Dropping foreign key"));
+        assert!(descriptions[1].starts_with("Adding foreign key"));
+
+        let drop_name = default_constraint_name(&target.name, &live.foreign_keys[0]);
+        let add_name = default_constraint_name(&target.name, &target.foreign_keys[0]);
+        assert_ne!(drop_name, add_name);
+    }
+}
@@ -0,0 +1,14 @@
+/// Quote a single identifier for use in generated SQL.
+fn quote_identifier(ident: &str) -> String {
+    format!("\"{}\"", ident)
+}
+
+/// Render a table (or other relation) name, qualifying it with its schema
+/// when one is set so actions can target tables outside the default
+/// `search_path`.
+pub fn qualify_table_name(schema: &Option<String>, name: &str) -> String {
+    match schema {
+        Some(schema) => format!("{}.{}", quote_identifier(schema), quote_identifier(name)),
+        None => quote_identifier(name),
+    }
+}
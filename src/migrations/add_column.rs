@@ -0,0 +1,151 @@
+use super::{Action, Column};
+use crate::{catalog::Catalog, db::Conn, helpers::qualify_table_name, schema::Schema};
+use serde::{Deserialize, Serialize};
+
+#[derive(Serialize, Deserialize, Debug)]
+pub struct AddColumn {
+    pub table: String,
+    #[serde(default)]
+    pub schema: Option<String>,
+    pub column: Column,
+    pub up: Option<String>,
+}
+
+impl AddColumn {
+    fn table_name(&self) -> String {
+        qualify_table_name(&self.schema, &self.table)
+    }
+
+    fn trigger_name(&self) -> String {
+        match &self.schema {
+            Some(schema) => format!("add_column_{}_{}_{}", schema, self.table, self.column.name),
+            None => format!("add_column_{}_{}", self.table, self.column.name),
+        }
+    }
+}
+
+#[typetag::serde(name = "add_column")]
+impl Action for AddColumn {
+    fn describe(&self, _catalog: &dyn Catalog) -> String {
+        format!(
+            "Adding column \"{}\" to \"{}\"",
+            self.column.name, self.table
+        )
+    }
+
+    fn validate(&self, catalog: &dyn Catalog) -> anyhow::Result<()> {
+        let table = catalog.find_table(self.schema.as_deref(), &self.table)?;
+
+        if table.columns.iter().any(|c| c.name == self.column.name) {
+            anyhow::bail!(
+                "column \"{}\" already exists on table \"{}\"",
+                self.column.name,
+                self.table,
+            );
+        }
+
+        // Checked here rather than in `run` so a typo'd `up`/default
+        // expression is caught during planning, before any action's DDL
+        // has run.
+        if let Some(up) = &self.up {
+            crate::validation::validate_expression(up, table)?;
+        }
+        if let Some(default) = &self.column.default {
+            crate::validation::validate_expression(default, table)?;
+        }
+
+        Ok(())
+    }
+
+    fn run(&self, db: &mut dyn Conn, _catalog: &dyn Catalog) -> anyhow::Result<()> {
+        // The column is added nullable, regardless of its final
+        // nullability, so the ALTER TABLE doesn't need to scan and rewrite
+        // every existing row. NOT NULL is enforced in `complete` instead.
+        let mut parts = vec![self.column.name.to_string(), self.column.data_type.to_string()];
+
+        if let Some(default) = &self.column.default {
+            parts.push("DEFAULT".to_string());
+            parts.push(default.to_string());
+        }
+
+        db.run(&format!(
+            "ALTER TABLE {table} ADD COLUMN {definition}",
+            table = self.table_name(),
+            definition = parts.join(" "),
+        ))?;
+
+        // Add an up trigger so rows written by clients still running the
+        // old schema, unaware of the new column, get a value populated.
+        if let Some(up) = &self.up {
+            let query = format!(
+                "
+                CREATE OR REPLACE FUNCTION {trigger_name}()
+                RETURNS TRIGGER AS $$
+                BEGIN
+                    NEW.{column_name} = {up};
+                    RETURN NEW;
+                END
+                $$ language 'plpgsql';
+
+                DROP TRIGGER IF EXISTS {trigger_name} ON {table};
+                CREATE TRIGGER {trigger_name} BEFORE UPDATE OR INSERT ON {table} FOR EACH ROW EXECUTE PROCEDURE {trigger_name}();
+                ",
+                column_name = self.column.name,
+                trigger_name = self.trigger_name(),
+                up = up,
+                table = self.table_name(),
+            );
+            db.run(&query)?;
+        }
+
+        Ok(())
+    }
+
+    fn complete(&self, db: &mut dyn Conn, _catalog: &dyn Catalog) -> anyhow::Result<()> {
+        if !self.column.nullable {
+            db.run(&format!(
+                "ALTER TABLE {table} ALTER COLUMN {column} SET NOT NULL",
+                table = self.table_name(),
+                column = self.column.name,
+            ))?;
+        }
+
+        db.run(&format!(
+            "
+            DROP TRIGGER IF EXISTS {trigger_name} ON {table};
+            DROP FUNCTION IF EXISTS {trigger_name};
+            ",
+            table = self.table_name(),
+            trigger_name = self.trigger_name(),
+        ))?;
+
+        Ok(())
+    }
+
+    fn update_schema(&self, schema: &mut Schema) -> anyhow::Result<()> {
+        let table = schema.find_table_mut(self.schema.as_deref(), &self.table)?;
+        table.add_column(crate::schema::Column {
+            name: self.column.name.to_string(),
+            real_name: None,
+            data_type: self.column.data_type.to_string(),
+            nullable: true,
+        });
+
+        Ok(())
+    }
+
+    fn abort(&self, db: &mut dyn Conn) -> anyhow::Result<()> {
+        db.run(&format!(
+            "
+            ALTER TABLE {table} DROP COLUMN IF EXISTS {column};
+            DROP TRIGGER IF EXISTS {trigger_name} ON {table};
+            DROP FUNCTION IF EXISTS {trigger_name};
+            ",
+            table = self.table_name(),
+            column = self.column.name,
+            trigger_name = self.trigger_name(),
+        ))?;
+
+        Ok(())
+    }
+}
@@ -0,0 +1,112 @@
+use super::{create_table::ForeignKey, Action};
+use crate::{catalog::Catalog, db::Conn, helpers::qualify_table_name, schema::Schema};
+use serde::{Deserialize, Serialize};
+
+#[derive(Serialize, Deserialize, Debug)]
+pub struct AddForeignKey {
+    pub table: String,
+    #[serde(default)]
+    pub schema: Option<String>,
+    pub name: String,
+    pub foreign_key: ForeignKey,
+}
+
+impl AddForeignKey {
+    fn table_name(&self) -> String {
+        qualify_table_name(&self.schema, &self.table)
+    }
+
+    fn constraint_name(&self) -> String {
+        self.name.to_string()
+    }
+}
+
+/// The constraint name a foreign key gets when one isn't given explicitly,
+/// e.g. when a migration is generated by diffing two schemas. Shared with
+/// `DropForeignKey` so a generated drop can find the constraint a generated
+/// add created.
+///
+/// Folds in the referenced table/columns, not just the local table/columns,
+/// so that changing what a foreign key points to (while keeping the same
+/// local columns) produces a different name for the old and new
+/// definitions, rather than a dropped and an added constraint racing for
+/// the same name.
+pub(crate) fn default_constraint_name(table: &str, foreign_key: &ForeignKey) -> String {
+    format!(
+        "fk_{}_{}_{}_{}",
+        table,
+        foreign_key.columns.join("_"),
+        foreign_key.referenced_table,
+        foreign_key.referenced_columns.join("_"),
+    )
+}
+
+#[typetag::serde(name = "add_foreign_key")]
+impl Action for AddForeignKey {
+    fn describe(&self, _catalog: &dyn Catalog) -> String {
+        format!(
+            "Adding foreign key from \"{}\" to \"{}\"",
+            self.table, self.foreign_key.referenced_table
+        )
+    }
+
+    fn validate(&self, catalog: &dyn Catalog) -> anyhow::Result<()> {
+        catalog.find_table(self.schema.as_deref(), &self.table)?;
+        catalog.find_table(
+            self.foreign_key.referenced_schema.as_deref(),
+            &self.foreign_key.referenced_table,
+        )?;
+
+        Ok(())
+    }
+
+    fn run(&self, db: &mut dyn Conn, _catalog: &dyn Catalog) -> anyhow::Result<()> {
+        // Add the constraint as NOT VALID so it's enforced for new/updated rows
+        // immediately without taking an ACCESS EXCLUSIVE lock to scan existing rows.
+        db.run(&format!(
+            "
+            ALTER TABLE {table}
+            ADD CONSTRAINT {name} FOREIGN KEY ({columns}) REFERENCES {referenced_table} ({referenced_columns}) NOT VALID
+            ",
+            table = self.table_name(),
+            name = self.constraint_name(),
+            columns = self.foreign_key.columns.join(", "),
+            referenced_table = qualify_table_name(
+                &self.foreign_key.referenced_schema,
+                &self.foreign_key.referenced_table
+            ),
+            referenced_columns = self.foreign_key.referenced_columns.join(", "),
+        ))?;
+
+        Ok(())
+    }
+
+    fn complete(&self, db: &mut dyn Conn, _catalog: &dyn Catalog) -> anyhow::Result<()> {
+        // Scans existing rows under a SHARE UPDATE EXCLUSIVE lock, so reads and
+        // writes against the table can continue while it runs.
+        db.run(&format!(
+            "ALTER TABLE {table} VALIDATE CONSTRAINT {name}",
+            table = self.table_name(),
+            name = self.constraint_name(),
+        ))?;
+
+        Ok(())
+    }
+
+    fn update_schema(&self, schema: &mut Schema) -> anyhow::Result<()> {
+        let table = schema.find_table_mut(self.schema.as_deref(), &self.table)?;
+        table.foreign_keys.push(self.foreign_key.clone());
+
+        Ok(())
+    }
+
+    fn abort(&self, db: &mut dyn Conn) -> anyhow::Result<()> {
+        db.run(&format!(
+            "ALTER TABLE {table} DROP CONSTRAINT IF EXISTS {name}",
+            table = self.table_name(),
+            name = self.constraint_name(),
+        ))?;
+
+        Ok(())
+    }
+}
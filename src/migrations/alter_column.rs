@@ -0,0 +1,97 @@
+use super::Action;
+use crate::{catalog::Catalog, db::Conn, helpers::qualify_table_name, schema::Schema};
+use serde::{Deserialize, Serialize};
+
+#[derive(Serialize, Deserialize, Debug)]
+pub struct AlterColumn {
+    pub table: String,
+    #[serde(default)]
+    pub schema: Option<String>,
+    pub column: String,
+    pub data_type: String,
+    pub using: Option<String>,
+    /// The column's type before this action, so `abort` can restore it.
+    /// Only known when this action was generated by diffing two schemas;
+    /// left `None` for a hand-written migration.
+    #[serde(default)]
+    pub previous_data_type: Option<String>,
+}
+
+impl AlterColumn {
+    fn table_name(&self) -> String {
+        qualify_table_name(&self.schema, &self.table)
+    }
+}
+
+#[typetag::serde(name = "alter_column")]
+impl Action for AlterColumn {
+    fn describe(&self, _catalog: &dyn Catalog) -> String {
+        format!(
+            "Altering type of column \"{}\" on \"{}\" to {}",
+            self.column, self.table, self.data_type
+        )
+    }
+
+    fn validate(&self, catalog: &dyn Catalog) -> anyhow::Result<()> {
+        let table = catalog.find_table(self.schema.as_deref(), &self.table)?;
+
+        if !table.columns.iter().any(|c| c.name == self.column) {
+            anyhow::bail!(
+                "column \"{}\" does not exist on table \"{}\"",
+                self.column,
+                self.table,
+            );
+        }
+
+        Ok(())
+    }
+
+    fn run(&self, db: &mut dyn Conn, _catalog: &dyn Catalog) -> anyhow::Result<()> {
+        let using = self
+            .using
+            .as_ref()
+            .map(|expr| format!(" USING {}", expr))
+            .unwrap_or_default();
+
+        db.run(&format!(
+            "ALTER TABLE {table} ALTER COLUMN {column} TYPE {data_type}{using}",
+            table = self.table_name(),
+            column = self.column,
+            data_type = self.data_type,
+            using = using,
+        ))?;
+
+        Ok(())
+    }
+
+    fn complete(&self, _db: &mut dyn Conn, _catalog: &dyn Catalog) -> anyhow::Result<()> {
+        // Do nothing
+        Ok(())
+    }
+
+    fn update_schema(&self, schema: &mut Schema) -> anyhow::Result<()> {
+        let table = schema.find_table_mut(self.schema.as_deref(), &self.table)?;
+        let column = table.find_column_mut(&self.column)?;
+        column.data_type = self.data_type.clone();
+
+        Ok(())
+    }
+
+    fn abort(&self, db: &mut dyn Conn) -> anyhow::Result<()> {
+        // run() commits before abort() can be invoked, possibly much
+        // later, so the column's new type has to be actively reverted
+        // rather than relied on to roll back with the rest of the
+        // migration. Nothing to revert to if the prior type wasn't
+        // recorded (a hand-written migration that didn't set it).
+        if let Some(previous_data_type) = &self.previous_data_type {
+            db.run(&format!(
+                "ALTER TABLE {table} ALTER COLUMN {column} TYPE {data_type}",
+                table = self.table_name(),
+                column = self.column,
+                data_type = previous_data_type,
+            ))?;
+        }
+
+        Ok(())
+    }
+}
@@ -0,0 +1,100 @@
+use super::Action;
+use crate::{catalog::Catalog, db::Conn, helpers::qualify_table_name, schema::Schema};
+use serde::{Deserialize, Serialize};
+
+#[derive(Serialize, Deserialize, Debug)]
+pub struct AlterPrimaryKey {
+    pub table: String,
+    #[serde(default)]
+    pub schema: Option<String>,
+    pub columns: Vec<String>,
+    /// The primary key's columns before this action, so `abort` can
+    /// restore them. Only known when this action was generated by diffing
+    /// two schemas; left `None` for a hand-written migration.
+    #[serde(default)]
+    pub previous_columns: Option<Vec<String>>,
+}
+
+impl AlterPrimaryKey {
+    fn table_name(&self) -> String {
+        qualify_table_name(&self.schema, &self.table)
+    }
+
+    fn constraint_name(&self) -> String {
+        format!("{}_pkey", self.table)
+    }
+}
+
+#[typetag::serde(name = "alter_primary_key")]
+impl Action for AlterPrimaryKey {
+    fn describe(&self, _catalog: &dyn Catalog) -> String {
+        format!(
+            "Altering primary key of \"{}\" to ({})",
+            self.table,
+            self.columns.join(", "),
+        )
+    }
+
+    fn validate(&self, catalog: &dyn Catalog) -> anyhow::Result<()> {
+        let table = catalog.find_table(self.schema.as_deref(), &self.table)?;
+
+        for column in &self.columns {
+            if !table.columns.iter().any(|c| &c.name == column) {
+                anyhow::bail!(
+                    "column \"{}\" does not exist on table \"{}\"",
+                    column,
+                    self.table,
+                );
+            }
+        }
+
+        Ok(())
+    }
+
+    fn run(&self, db: &mut dyn Conn, _catalog: &dyn Catalog) -> anyhow::Result<()> {
+        db.run(&format!(
+            "
+            ALTER TABLE {table} DROP CONSTRAINT IF EXISTS {constraint_name};
+            ALTER TABLE {table} ADD CONSTRAINT {constraint_name} PRIMARY KEY ({columns});
+            ",
+            table = self.table_name(),
+            constraint_name = self.constraint_name(),
+            columns = self.columns.join(", "),
+        ))?;
+
+        Ok(())
+    }
+
+    fn complete(&self, _db: &mut dyn Conn, _catalog: &dyn Catalog) -> anyhow::Result<()> {
+        // Do nothing
+        Ok(())
+    }
+
+    fn update_schema(&self, schema: &mut Schema) -> anyhow::Result<()> {
+        let table = schema.find_table_mut(self.schema.as_deref(), &self.table)?;
+        table.primary_key = self.columns.clone();
+
+        Ok(())
+    }
+
+    fn abort(&self, db: &mut dyn Conn) -> anyhow::Result<()> {
+        // run() commits before abort() can be invoked, possibly much
+        // later, so the new primary key has to be actively reverted rather
+        // than relied on to roll back with the rest of the migration.
+        // Nothing to revert to if the prior key wasn't recorded (a
+        // hand-written migration that didn't set it).
+        if let Some(previous_columns) = &self.previous_columns {
+            db.run(&format!(
+                "
+                ALTER TABLE {table} DROP CONSTRAINT IF EXISTS {constraint_name};
+                ALTER TABLE {table} ADD CONSTRAINT {constraint_name} PRIMARY KEY ({columns});
+                ",
+                table = self.table_name(),
+                constraint_name = self.constraint_name(),
+                columns = previous_columns.join(", "),
+            ))?;
+        }
+
+        Ok(())
+    }
+}
@@ -1,6 +1,8 @@
 use super::{Action, Column};
 use crate::{
+    catalog::Catalog,
     db::Conn,
+    helpers::qualify_table_name,
     schema::{Schema, Table},
 };
 use serde::{Deserialize, Serialize};
@@ -8,25 +10,91 @@ use serde::{Deserialize, Serialize};
 #[derive(Serialize, Deserialize, Debug)]
 pub struct CreateTable {
     pub name: String,
+    #[serde(default)]
+    pub schema: Option<String>,
     pub columns: Vec<Column>,
     pub primary_key: Vec<String>,
     pub foreign_keys: Vec<ForeignKey>,
+    #[serde(default)]
+    pub unique: Vec<UniqueConstraint>,
+    #[serde(default)]
+    pub checks: Vec<CheckConstraint>,
 }
 
-#[derive(Serialize, Deserialize, Debug)]
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq, Eq)]
 pub struct ForeignKey {
     pub columns: Vec<String>,
+    #[serde(default)]
+    pub referenced_schema: Option<String>,
     pub referenced_table: String,
     pub referenced_columns: Vec<String>,
 }
 
+#[derive(Serialize, Deserialize, Debug, Clone)]
+pub struct UniqueConstraint {
+    pub name: String,
+    pub columns: Vec<String>,
+    #[serde(default)]
+    pub nulls_not_distinct: bool,
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone)]
+pub struct CheckConstraint {
+    pub name: String,
+    pub expression: String,
+}
+
+impl CreateTable {
+    /// Build the `Table` this action will create, so both schema-tracking
+    /// and pre-flight validation can reuse the same view of its columns.
+    fn as_table(&self) -> Table {
+        let mut table = Table::new(self.name.to_string());
+        table.schema = self.schema.clone();
+        table.primary_key = self.primary_key.clone();
+        table.foreign_keys = self.foreign_keys.clone();
+        table.unique = self.unique.clone();
+        table.checks = self.checks.clone();
+
+        for column in &self.columns {
+            table.add_column(crate::schema::Column {
+                name: column.name.to_string(),
+                real_name: None,
+                data_type: column.data_type.to_string(),
+                nullable: column.nullable,
+            });
+        }
+
+        table
+    }
+}
+
 #[typetag::serde(name = "create_table")]
 impl Action for CreateTable {
-    fn describe(&self) -> String {
+    fn describe(&self, _catalog: &dyn Catalog) -> String {
         format!("Creating table \"{}\"", self.name)
     }
 
-    fn run(&self, db: &mut dyn Conn, _schema: &Schema) -> anyhow::Result<()> {
+    fn validate(&self, catalog: &dyn Catalog) -> anyhow::Result<()> {
+        if catalog
+            .find_table(self.schema.as_deref(), &self.name)
+            .is_ok()
+        {
+            anyhow::bail!("table \"{}\" already exists", self.name);
+        }
+
+        // Checked during planning rather than in `run`, so a typo'd
+        // default expression is caught before the CREATE TABLE runs.
+        let table = self.as_table();
+        for column in &self.columns {
+            if let Some(default) = &column.default {
+                crate::validation::validate_expression(default, &table)?;
+            }
+        }
+
+        Ok(())
+    }
+
+    fn run(&self, db: &mut dyn Conn, _catalog: &dyn Catalog) -> anyhow::Result<()> {
         let mut definition_rows: Vec<String> = self
             .columns
             .iter()
@@ -53,45 +121,62 @@ impl Action for CreateTable {
             definition_rows.push(format!(
                 "FOREIGN KEY ({columns}) REFERENCES {table} ({referenced_columns})",
                 columns = foreign_key.columns.join(", "),
-                table = foreign_key.referenced_table,
+                table = qualify_table_name(
+                    &foreign_key.referenced_schema,
+                    &foreign_key.referenced_table
+                ),
                 referenced_columns = foreign_key.referenced_columns.join(", "),
             ));
         }
 
+        for unique in &self.unique {
+            let nulls_not_distinct = if unique.nulls_not_distinct {
+                " NULLS NOT DISTINCT"
+            } else {
+                ""
+            };
+
+            definition_rows.push(format!(
+                "CONSTRAINT {name} UNIQUE{nulls_not_distinct} ({columns})",
+                name = unique.name,
+                columns = unique.columns.join(", "),
+            ));
+        }
+
+        for check in &self.checks {
+            definition_rows.push(format!(
+                "CONSTRAINT {name} CHECK ({expression})",
+                name = check.name,
+                expression = check.expression,
+            ));
+        }
+
         db.run(&format!(
             "CREATE TABLE {} (
                 {}
             )",
-            self.name,
+            qualify_table_name(&self.schema, &self.name),
             definition_rows.join(",\n"),
         ))?;
         Ok(())
     }
 
-    fn complete(&self, _db: &mut dyn Conn, _schema: &Schema) -> anyhow::Result<()> {
+    fn complete(&self, _db: &mut dyn Conn, _catalog: &dyn Catalog) -> anyhow::Result<()> {
         // Do nothing
         Ok(())
     }
 
     fn update_schema(&self, schema: &mut Schema) -> anyhow::Result<()> {
-        let mut table = Table::new(self.name.to_string());
-        table.primary_key = self.primary_key.clone();
-
-        for column in &self.columns {
-            table.add_column(crate::schema::Column {
-                name: column.name.to_string(),
-                real_name: None,
-                data_type: column.data_type.to_string(),
-                nullable: column.nullable,
-            });
-        }
-        schema.add_table(table);
+        schema.add_table(self.as_table());
 
         Ok(())
     }
 
     fn abort(&self, db: &mut dyn Conn) -> anyhow::Result<()> {
-        let query = format!("DROP TABLE IF EXISTS {table}", table = self.name,);
+        let query = format!(
+            "DROP TABLE IF EXISTS {table}",
+            table = qualify_table_name(&self.schema, &self.name),
+        );
         db.run(&query)?;
 
         Ok(())
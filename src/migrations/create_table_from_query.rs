@@ -0,0 +1,94 @@
+use super::Action;
+use crate::{catalog::Catalog, db::Conn, helpers::qualify_table_name, schema::Schema};
+use serde::{Deserialize, Serialize};
+
+#[derive(Serialize, Deserialize, Debug)]
+pub struct CreateTableFromQuery {
+    pub name: String,
+    #[serde(default)]
+    pub schema: Option<String>,
+    pub columns: Vec<String>,
+    pub query: String,
+}
+
+impl CreateTableFromQuery {
+    fn table_name(&self) -> String {
+        qualify_table_name(&self.schema, &self.name)
+    }
+}
+
+#[typetag::serde(name = "create_table_from_query")]
+impl Action for CreateTableFromQuery {
+    fn describe(&self, _catalog: &dyn Catalog) -> String {
+        format!(
+            "Creating table \"{}\" from a query, for backfilling",
+            self.name
+        )
+    }
+
+    fn validate(&self, catalog: &dyn Catalog) -> anyhow::Result<()> {
+        if catalog
+            .find_table(self.schema.as_deref(), &self.name)
+            .is_ok()
+        {
+            anyhow::bail!("table \"{}\" already exists", self.name);
+        }
+
+        Ok(())
+    }
+
+    fn run(&self, db: &mut dyn Conn, _catalog: &dyn Catalog) -> anyhow::Result<()> {
+        // Create the table with the query's shape but no rows, then fill it
+        // with a separate INSERT ... SELECT. Both statements run here, one
+        // after the other, so the backfill has finished when `run` returns.
+        db.run(&format!(
+            "CREATE TABLE {table} ({columns}) AS {query} WITH NO DATA",
+            table = self.table_name(),
+            columns = self.columns.join(", "),
+            query = self.query,
+        ))?;
+
+        db.run(&format!(
+            "INSERT INTO {table} {query}",
+            table = self.table_name(),
+            query = self.query,
+        ))?;
+
+        Ok(())
+    }
+
+    fn complete(&self, _db: &mut dyn Conn, _catalog: &dyn Catalog) -> anyhow::Result<()> {
+        // Do nothing
+        Ok(())
+    }
+
+    fn update_schema(&self, schema: &mut Schema) -> anyhow::Result<()> {
+        let mut table = crate::schema::Table::new(self.name.to_string());
+        table.schema = self.schema.clone();
+
+        // update_schema only has the in-memory Schema to work with, not a
+        // live connection, so the resulting columns can't be inferred from
+        // the query; they must be given explicitly.
+        for column in &self.columns {
+            table.add_column(crate::schema::Column {
+                name: column.to_string(),
+                real_name: None,
+                data_type: String::new(),
+                nullable: true,
+            });
+        }
+
+        schema.add_table(table);
+
+        Ok(())
+    }
+
+    fn abort(&self, db: &mut dyn Conn) -> anyhow::Result<()> {
+        db.run(&format!(
+            "DROP TABLE IF EXISTS {table}",
+            table = self.table_name(),
+        ))?;
+
+        Ok(())
+    }
+}
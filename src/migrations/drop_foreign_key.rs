@@ -0,0 +1,64 @@
+use super::Action;
+use crate::{catalog::Catalog, db::Conn, helpers::qualify_table_name, schema::Schema};
+use serde::{Deserialize, Serialize};
+
+#[derive(Serialize, Deserialize, Debug)]
+pub struct DropForeignKey {
+    pub table: String,
+    #[serde(default)]
+    pub schema: Option<String>,
+    pub name: String,
+}
+
+impl DropForeignKey {
+    fn table_name(&self) -> String {
+        qualify_table_name(&self.schema, &self.table)
+    }
+}
+
+#[typetag::serde(name = "drop_foreign_key")]
+impl Action for DropForeignKey {
+    fn describe(&self, _catalog: &dyn Catalog) -> String {
+        format!(
+            "Dropping foreign key \"{}\" from \"{}\"",
+            self.name, self.table
+        )
+    }
+
+    fn validate(&self, catalog: &dyn Catalog) -> anyhow::Result<()> {
+        catalog.find_table(self.schema.as_deref(), &self.table)?;
+
+        Ok(())
+    }
+
+    fn run(&self, db: &mut dyn Conn, _catalog: &dyn Catalog) -> anyhow::Result<()> {
+        db.run(&format!(
+            "ALTER TABLE {table} DROP CONSTRAINT IF EXISTS {name}",
+            table = self.table_name(),
+            name = self.name,
+        ))?;
+
+        Ok(())
+    }
+
+    fn complete(&self, _db: &mut dyn Conn, _catalog: &dyn Catalog) -> anyhow::Result<()> {
+        // Do nothing
+        Ok(())
+    }
+
+    fn update_schema(&self, schema: &mut Schema) -> anyhow::Result<()> {
+        let table = schema.find_table_mut(self.schema.as_deref(), &self.table)?;
+        table.foreign_keys.retain(|fk| {
+            super::add_foreign_key::default_constraint_name(&self.table, fk) != self.name
+        });
+
+        Ok(())
+    }
+
+    fn abort(&self, _db: &mut dyn Conn) -> anyhow::Result<()> {
+        // The dropped constraint's definition isn't recorded here, so it
+        // can't be recreated; the outer migration transaction rolls back
+        // the DROP CONSTRAINT along with everything else in the migration.
+        Ok(())
+    }
+}
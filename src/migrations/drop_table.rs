@@ -0,0 +1,72 @@
+use super::Action;
+use crate::{catalog::Catalog, db::Conn, helpers::qualify_table_name, schema::Schema};
+use serde::{Deserialize, Serialize};
+
+#[derive(Serialize, Deserialize, Debug)]
+pub struct DropTable {
+    pub name: String,
+    #[serde(default)]
+    pub schema: Option<String>,
+}
+
+impl DropTable {
+    fn table_name(&self) -> String {
+        qualify_table_name(&self.schema, &self.name)
+    }
+
+    fn staging_name(&self) -> String {
+        format!("reshape_dropped_{}", self.name)
+    }
+}
+
+#[typetag::serde(name = "drop_table")]
+impl Action for DropTable {
+    fn describe(&self, _catalog: &dyn Catalog) -> String {
+        format!("Dropping table \"{}\"", self.name)
+    }
+
+    fn validate(&self, catalog: &dyn Catalog) -> anyhow::Result<()> {
+        catalog.find_table(self.schema.as_deref(), &self.name)?;
+
+        Ok(())
+    }
+
+    fn run(&self, db: &mut dyn Conn, _catalog: &dyn Catalog) -> anyhow::Result<()> {
+        // Rename rather than drop outright, so the table can still be
+        // recovered if the migration is aborted. The actual DROP happens in
+        // `complete`, once the migration can no longer be aborted.
+        db.run(&format!(
+            "ALTER TABLE {table} RENAME TO {staging}",
+            table = self.table_name(),
+            staging = self.staging_name(),
+        ))?;
+
+        Ok(())
+    }
+
+    fn complete(&self, db: &mut dyn Conn, _catalog: &dyn Catalog) -> anyhow::Result<()> {
+        db.run(&format!(
+            "DROP TABLE IF EXISTS {staging}",
+            staging = qualify_table_name(&self.schema, &self.staging_name()),
+        ))?;
+
+        Ok(())
+    }
+
+    fn update_schema(&self, schema: &mut Schema) -> anyhow::Result<()> {
+        schema.remove_table(self.schema.as_deref(), &self.name);
+
+        Ok(())
+    }
+
+    fn abort(&self, db: &mut dyn Conn) -> anyhow::Result<()> {
+        db.run(&format!(
+            "ALTER TABLE {staging} RENAME TO {table}",
+            staging = qualify_table_name(&self.schema, &self.staging_name()),
+            // RENAME TO takes a bare name; the table stays in its schema.
+            table = qualify_table_name(&None, &self.name),
+        ))?;
+
+        Ok(())
+    }
+}
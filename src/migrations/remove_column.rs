@@ -1,41 +1,71 @@
 use super::Action;
-use crate::{db::Conn, schema::Schema};
+use crate::{catalog::Catalog, db::Conn, helpers::qualify_table_name, schema::Schema};
 use serde::{Deserialize, Serialize};
 
 #[derive(Serialize, Deserialize, Debug)]
 pub struct RemoveColumn {
     pub table: String,
+    #[serde(default)]
+    pub schema: Option<String>,
     pub column: String,
     pub down: Option<String>,
 }
 
 impl RemoveColumn {
+    fn table_name(&self) -> String {
+        qualify_table_name(&self.schema, &self.table)
+    }
+
     fn trigger_name(&self) -> String {
-        format!("remove_column_{}_{}", self.table, self.column)
+        match &self.schema {
+            Some(schema) => format!("remove_column_{}_{}_{}", schema, self.table, self.column),
+            None => format!("remove_column_{}_{}", self.table, self.column),
+        }
     }
 }
 
 #[typetag::serde(name = "remove_column")]
 impl Action for RemoveColumn {
-    fn describe(&self) -> String {
+    fn describe(&self, _catalog: &dyn Catalog) -> String {
         format!(
             "Removing column \"{}\" from \"{}\"",
             self.column, self.table
         )
     }
 
-    fn run(&self, db: &mut dyn Conn, schema: &Schema) -> anyhow::Result<()> {
+    fn validate(&self, catalog: &dyn Catalog) -> anyhow::Result<()> {
+        let table = catalog.find_table(self.schema.as_deref(), &self.table)?;
+
+        if !table.columns.iter().any(|c| c.name == self.column) {
+            anyhow::bail!(
+                "column \"{}\" does not exist on table \"{}\"",
+                self.column,
+                self.table,
+            );
+        }
+
+        // Checked during planning rather than in `run`, so a `down`
+        // expression referencing a misspelled column is caught before the
+        // column it reads is actually dropped.
+        if let Some(down) = &self.down {
+            crate::validation::validate_expression(down, table)?;
+        }
+
+        Ok(())
+    }
+
+    fn run(&self, db: &mut dyn Conn, catalog: &dyn Catalog) -> anyhow::Result<()> {
         // Add down trigger
         if let Some(down) = &self.down {
-            let table = schema.find_table(&self.table)?;
+            let table = catalog.find_table(self.schema.as_deref(), &self.table)?;
 
             let declarations: Vec<String> = table
                 .columns
                 .iter()
                 .map(|column| {
                     format!(
-                        "{name} public.{table}.{name}%TYPE := NEW.{name};",
-                        table = table.name,
+                        "{name} {table}.{name}%TYPE := NEW.{name};",
+                        table = self.table_name(),
                         name = column.name,
                     )
                 })
@@ -63,7 +93,7 @@ impl Action for RemoveColumn {
                 column_name = self.column,
                 trigger_name = self.trigger_name(),
                 down = down,
-                table = self.table,
+                table = self.table_name(),
                 declarations = declarations.join("\n"),
             );
             db.run(&query)?;
@@ -72,7 +102,7 @@ impl Action for RemoveColumn {
         Ok(())
     }
 
-    fn complete(&self, db: &mut dyn Conn, _schema: &Schema) -> anyhow::Result<()> {
+    fn complete(&self, db: &mut dyn Conn, _catalog: &dyn Catalog) -> anyhow::Result<()> {
         // Remove column, function and trigger
         let query = format!(
             "
@@ -82,7 +112,7 @@ impl Action for RemoveColumn {
             DROP TRIGGER IF EXISTS {trigger_name} ON {table};
             DROP FUNCTION IF EXISTS {trigger_name};
             ",
-            table = self.table,
+            table = self.table_name(),
             column = self.column,
             trigger_name = self.trigger_name(),
         );
@@ -92,7 +122,7 @@ impl Action for RemoveColumn {
     }
 
     fn update_schema(&self, schema: &mut Schema) -> anyhow::Result<()> {
-        let table = schema.find_table_mut(&self.table)?;
+        let table = schema.find_table_mut(self.schema.as_deref(), &self.table)?;
         table.remove_column(&self.column);
 
         Ok(())
@@ -105,7 +135,7 @@ impl Action for RemoveColumn {
             DROP TRIGGER IF EXISTS {trigger_name} ON {table};
             DROP FUNCTION IF EXISTS {trigger_name};
             ",
-            table = self.table,
+            table = self.table_name(),
             trigger_name = self.trigger_name(),
         ))?;
 
@@ -0,0 +1,180 @@
+// Parses the SQL expression fragments used in `up`/`down` triggers and
+// column defaults. Requires the `sqlparser` crate as a dependency.
+use crate::schema::{Column, Table};
+use anyhow::bail;
+use sqlparser::ast::{Expr, FunctionArg, FunctionArgExpr, SelectItem, SetExpr, Statement};
+use sqlparser::dialect::PostgreSqlDialect;
+use sqlparser::parser::Parser;
+use std::collections::HashSet;
+
+/// Parse a raw SQL expression fragment, such as the ones used in `up`/`down`
+/// triggers and column defaults.
+///
+/// Expression fragments aren't valid SQL on their own, so they're parsed as
+/// the projection of a throwaway `SELECT`.
+pub fn parse_expression(expression: &str) -> anyhow::Result<Expr> {
+    let dialect = PostgreSqlDialect {};
+    let sql = format!("SELECT {}", expression);
+    let mut statements = Parser::parse_sql(&dialect, &sql)?;
+
+    let Some(Statement::Query(query)) = statements.pop() else {
+        bail!("expression \"{}\" is not a valid SQL expression", expression);
+    };
+
+    let SetExpr::Select(select) = *query.body else {
+        bail!("expression \"{}\" is not a valid SQL expression", expression);
+    };
+
+    let mut projection = select.projection.into_iter();
+    let Some(item) = projection.next() else {
+        bail!("expression \"{}\" is not a valid SQL expression", expression);
+    };
+
+    // A second projection item means the raw string contains a stray
+    // top-level comma (e.g. "user_id, typo_column"): the whole string gets
+    // spliced verbatim into the trigger body later, so only the first item
+    // being checked here isn't good enough — reject it outright.
+    if projection.next().is_some() {
+        bail!("expression \"{}\" is not a valid SQL expression", expression);
+    }
+
+    match item {
+        // An alias validates a different string than the one actually
+        // substituted into the trigger body (which is `expression` as
+        // written, alias and all) — invalid PL/pgSQL assignment syntax
+        // that would only surface once spliced in, not here.
+        SelectItem::UnnamedExpr(expr) => Ok(expr),
+        _ => bail!("expression \"{}\" is not a valid SQL expression", expression),
+    }
+}
+
+/// Collect the plain or compound column identifiers referenced by an
+/// expression. Only identifiers, binary and unary operators, nested and
+/// cast expressions, unnamed function arguments and CASE expressions are
+/// walked; columns inside any other kind of expression (e.g. IS NULL,
+/// IN lists, BETWEEN, LIKE or subqueries) are not collected.
+pub fn referenced_columns(expr: &Expr) -> HashSet<String> {
+    let mut columns = HashSet::new();
+    collect_columns(expr, &mut columns);
+    columns
+}
+
+fn collect_columns(expr: &Expr, columns: &mut HashSet<String>) {
+    match expr {
+        Expr::Identifier(ident) => {
+            columns.insert(ident.value.clone());
+        }
+        Expr::CompoundIdentifier(parts) => {
+            if let Some(last) = parts.last() {
+                columns.insert(last.value.clone());
+            }
+        }
+        Expr::BinaryOp { left, right, .. } => {
+            collect_columns(left, columns);
+            collect_columns(right, columns);
+        }
+        Expr::UnaryOp { expr, .. } | Expr::Nested(expr) | Expr::Cast { expr, .. } => {
+            collect_columns(expr, columns);
+        }
+        Expr::Function(function) => {
+            for arg in &function.args {
+                if let FunctionArg::Unnamed(FunctionArgExpr::Expr(expr)) = arg {
+                    collect_columns(expr, columns);
+                }
+            }
+        }
+        Expr::Case {
+            operand,
+            conditions,
+            results,
+            else_result,
+        } => {
+            if let Some(operand) = operand {
+                collect_columns(operand, columns);
+            }
+            for expr in conditions.iter().chain(results.iter()) {
+                collect_columns(expr, columns);
+            }
+            if let Some(else_result) = else_result {
+                collect_columns(else_result, columns);
+            }
+        }
+        _ => {}
+    }
+}
+
+/// Parse `expression` and ensure every column `referenced_columns` finds in
+/// it exists on `table`, so a migration with a typo in an `up`/`down`
+/// expression or a column default fails the migration plan up front, before
+/// any DDL runs.
+pub fn validate_expression(expression: &str, table: &Table) -> anyhow::Result<()> {
+    let expr = parse_expression(expression)?;
+
+    for column in referenced_columns(&expr) {
+        if !table.columns.iter().any(|c| c.name == column) {
+            bail!(
+                "expression \"{}\" references unknown column \"{}\" on table \"{}\"",
+                expression,
+                column,
+                table.name,
+            );
+        }
+    }
+
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn table_with_columns(names: &[&str]) -> Table {
+        let mut table = Table::new("users".to_string());
+        for name in names {
+            table.add_column(Column {
+                name: name.to_string(),
+                real_name: None,
+                data_type: "text".to_string(),
+                nullable: true,
+            });
+        }
+        table
+    }
+
+    #[test]
+    fn validate_expression_accepts_known_columns() {
+        let table = table_with_columns(&["id", "email"]);
+        assert!(validate_expression("lower(email)", &table).is_ok());
+    }
+
+    #[test]
+    fn validate_expression_rejects_unknown_column() {
+        let table = table_with_columns(&["id", "email"]);
+        assert!(validate_expression("lower(emali)", &table).is_err());
+    }
+
+    #[test]
+    fn parse_expression_rejects_stray_top_level_comma() {
+        // A second projection item means a stray comma snuck into the raw
+        // string; only checking the first would let "id, typo_column"
+        // through with just "id" validated.
+        assert!(parse_expression("id, typo_column").is_err());
+    }
+
+    #[test]
+    fn parse_expression_rejects_alias() {
+        // The raw string (alias included) is what actually gets spliced
+        // into the trigger body, so an aliased expression must be rejected
+        // even though the underlying expression is fine on its own.
+        assert!(parse_expression("id AS renamed").is_err());
+    }
+
+    #[test]
+    fn referenced_columns_finds_columns_inside_a_function_call() {
+        let expr = parse_expression("coalesce(email, 'unknown')").unwrap();
+        assert_eq!(
+            referenced_columns(&expr),
+            ["email".to_string()].into_iter().collect()
+        );
+    }
+}